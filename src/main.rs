@@ -1,18 +1,22 @@
 use futures::stream::FuturesUnordered;
 use futures::StreamExt;
-use once_cell::sync::Lazy;
+use once_cell::sync::{Lazy, OnceCell};
 use rand::{thread_rng, Rng};
+use regex::Regex;
 use reqwest::Client;
+use serde::{Deserialize, Serialize};
 use std::{
+    collections::{HashMap, HashSet},
     fs::File,
     io::Write,
     sync::{
-        atomic::{AtomicBool, Ordering},
-        Mutex,
+        atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
+        Arc, Mutex,
     },
 };
 use tokio::{
     signal,
+    sync::Semaphore,
     task,
     time::{sleep, Duration},
 };
@@ -26,12 +30,236 @@ const USER_AGENTS: &[&str] = &[
 const UID_START: u32 = 10_000;
 const UID_END: u32 = 80_000;
 const QUESTION_COUNT: u32 = 300;
-const MAX_CONCURRENCY: usize = 5; // reduced for rate-limiting friendliness
+const DEFAULT_CONCURRENCY: usize = 5; // reduced for rate-limiting friendliness
+const MAX_REDIRECT_HOPS: u32 = 5; // mirrors the MAX_REDR cap used by the openethereum fetch client
+const DEFAULT_MAX_BODY_BYTES: usize = 256 * 1024; // mirrors the openethereum fetch client's MAX_SIZE guard
+const CHECKPOINT_FLUSH_INTERVAL: u32 = 500; // how many checks between on-disk checkpoint flushes
+
+// Adaptive pacing: the minimum gap every worker must leave between requests,
+// shared globally so the whole fleet converges on a safe rate together
+// instead of each worker guessing independently.
+const MIN_INTERVAL_FLOOR_MS: u64 = 100;
+const MIN_INTERVAL_CEILING_MS: u64 = 30_000;
+const BACKOFF_FACTOR: f64 = 1.5;
+const DECAY_FACTOR: f64 = 0.9;
+const CLEAN_STREAK_FOR_DECAY: u32 = 20;
 
 static STOP_REQUESTED: AtomicBool = AtomicBool::new(false);
 static VALID_LOGGER: Lazy<Mutex<File>> = Lazy::new(|| {
     Mutex::new(File::create("valid_urls.log").expect("Unable to create log file"))
 });
+static REPORT_LOGGER: OnceCell<Mutex<File>> = OnceCell::new();
+static MIN_INTERVAL_MS: AtomicU64 = AtomicU64::new(MIN_INTERVAL_FLOOR_MS);
+static CLEAN_STREAK: AtomicU32 = AtomicU32::new(0);
+
+/// A 429 was observed: back off the shared pacing interval and reset the clean streak.
+fn record_rate_limited() {
+    CLEAN_STREAK.store(0, Ordering::SeqCst);
+    let _ = MIN_INTERVAL_MS.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |cur| {
+        Some((((cur as f64) * BACKOFF_FACTOR) as u64).min(MIN_INTERVAL_CEILING_MS))
+    });
+}
+
+/// A clean (200/404) response was observed: after a long enough streak, decay
+/// the pacing interval back toward the floor.
+fn record_clean_response() {
+    let streak = CLEAN_STREAK.fetch_add(1, Ordering::SeqCst) + 1;
+    if streak >= CLEAN_STREAK_FOR_DECAY {
+        CLEAN_STREAK.store(0, Ordering::SeqCst);
+        let _ = MIN_INTERVAL_MS.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |cur| {
+            Some((((cur as f64) * DECAY_FACTOR) as u64).max(MIN_INTERVAL_FLOOR_MS))
+        });
+    }
+}
+
+/// The outcome of probing a single candidate URL, mirroring the shape of the
+/// awesome-rust link checker's `CheckerError` so results can be serialized
+/// and consumed by other tools instead of only being printed.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+enum CheckResult {
+    Ok { url: String, status: u16 },
+    HttpError { status: u16, location: Option<String> },
+    Redirect { from: String, to: String, status: u16 },
+    /// A redirect hit under `--on-redirect fail`: treated as an immediate
+    /// dead end rather than a candidate that could be retried or followed.
+    RedirectFailed { from: String, to: String, status: u16 },
+    SoftNotFound { status: u16, reason: String },
+    RateLimited { retries: u32 },
+    Timeout,
+    Transport { error: String },
+    /// The uid was never actually probed because a stop was already
+    /// requested: not a real probe outcome, so it must not advance the
+    /// resume floor or appear in `--report` output.
+    Skipped,
+}
+
+impl CheckResult {
+    fn is_found(&self) -> bool {
+        matches!(self, CheckResult::Ok { .. })
+    }
+}
+
+/// How a 3xx response should be treated, selected with `--on-redirect`.
+#[derive(Debug, Clone, Copy)]
+enum RedirectPolicy {
+    /// Record the redirect and move on to the next candidate (default).
+    Skip,
+    /// Re-issue the request against the `Location` target, up to `MAX_REDIRECT_HOPS` hops.
+    Follow,
+    /// Treat a redirect as an immediate dead end, with no retries.
+    Fail,
+}
+
+impl RedirectPolicy {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "skip" => Some(RedirectPolicy::Skip),
+            "follow" => Some(RedirectPolicy::Follow),
+            "fail" => Some(RedirectPolicy::Fail),
+            _ => None,
+        }
+    }
+}
+
+/// Per-run settings shared by every `check_url` call, bundled together now
+/// that the list of knobs (concurrency, redirects, body matching) has grown
+/// past what's comfortable as separate parameters.
+#[derive(Clone)]
+struct CheckConfig {
+    semaphore: Arc<Semaphore>,
+    redirect_policy: RedirectPolicy,
+    match_regex: Option<Arc<Regex>>,
+    not_match_regex: Option<Arc<Regex>>,
+    max_body_bytes: usize,
+}
+
+/// On-disk progress for a `--resume`-able scan: the lowest uid not yet
+/// confirmed checked for each question still in progress (the resume point),
+/// which questions are already solved, and the URL that solved them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Checkpoint {
+    /// Per question: the lowest uid not yet confirmed checked.
+    progress: HashMap<u32, u32>,
+    /// Per question: uids at or above `progress[qnum]` that finished out of
+    /// order (the concurrent sweep checks uids in a spread, not strictly in
+    /// order), held here until the gap below them closes and they can be
+    /// folded into `progress`.
+    pending: HashMap<u32, HashSet<u32>>,
+    solved: HashSet<u32>,
+    found_urls: HashMap<u32, String>,
+}
+
+struct CheckpointStore {
+    path: String,
+    state: Mutex<Checkpoint>,
+}
+
+static CHECKPOINT_STORE: OnceCell<CheckpointStore> = OnceCell::new();
+static CHECKPOINT_TICK: AtomicU32 = AtomicU32::new(0);
+
+/// Load a checkpoint from `path` if it exists and parses, otherwise start fresh.
+fn load_checkpoint(path: &str) -> Checkpoint {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn save_checkpoint() {
+    if let Some(store) = CHECKPOINT_STORE.get() {
+        let state = store.state.lock().unwrap();
+        if let Ok(json) = serde_json::to_string_pretty(&*state) {
+            std::fs::write(&store.path, json).ok();
+        }
+    }
+}
+
+fn is_question_solved(qnum: u32) -> bool {
+    CHECKPOINT_STORE
+        .get()
+        .map(|store| store.state.lock().unwrap().solved.contains(&qnum))
+        .unwrap_or(false)
+}
+
+/// The uid to resume a question's sweep from: the lowest uid not yet
+/// confirmed checked before the scan was last interrupted.
+fn resume_uid(qnum: u32) -> u32 {
+    CHECKPOINT_STORE
+        .get()
+        .and_then(|store| store.state.lock().unwrap().progress.get(&qnum).copied())
+        .unwrap_or(UID_START)
+        .max(UID_START)
+}
+
+/// Record that `uid` finished checking for `qnum`, flushing to disk every
+/// `CHECKPOINT_FLUSH_INTERVAL` calls so a crash loses at most a short window.
+///
+/// The concurrent sweep in `find_valid_url_for_question` completes uids out
+/// of order, so a single "max uid seen" watermark isn't safe to resume from:
+/// a higher uid can finish while a lower one is still in flight, and
+/// resuming past the lower uid would silently skip it forever. Instead this
+/// only advances the on-disk floor once the *contiguous* run of completed
+/// uids reaches it, parking anything that finishes ahead of the gap in
+/// `pending` until the gap closes.
+fn record_progress(qnum: u32, uid: u32) {
+    if let Some(store) = CHECKPOINT_STORE.get() {
+        {
+            let mut state = store.state.lock().unwrap();
+            let mut floor = *state.progress.entry(qnum).or_insert(UID_START);
+            if uid == floor {
+                floor += 1;
+                if let Some(pending) = state.pending.get_mut(&qnum) {
+                    while pending.remove(&floor) {
+                        floor += 1;
+                    }
+                    if pending.is_empty() {
+                        state.pending.remove(&qnum);
+                    }
+                }
+                state.progress.insert(qnum, floor);
+            } else if uid > floor {
+                state.pending.entry(qnum).or_default().insert(uid);
+            }
+            // uid < floor was already folded in by an earlier call; nothing to do.
+        }
+        if CHECKPOINT_TICK.fetch_add(1, Ordering::SeqCst) % CHECKPOINT_FLUSH_INTERVAL == 0 {
+            save_checkpoint();
+        }
+    }
+}
+
+/// Record that a question has been solved and flush immediately.
+fn record_solved(qnum: u32, url: &str) {
+    if let Some(store) = CHECKPOINT_STORE.get() {
+        {
+            let mut state = store.state.lock().unwrap();
+            state.solved.insert(qnum);
+            state.found_urls.insert(qnum, url.to_string());
+            state.progress.remove(&qnum);
+            state.pending.remove(&qnum);
+        }
+        save_checkpoint();
+    }
+}
+
+/// One line of the `--report` output: which candidate was probed, plus its result.
+#[derive(Debug, Clone, Serialize)]
+struct CheckRecord {
+    qnum: u32,
+    uid: u32,
+    #[serde(flatten)]
+    result: CheckResult,
+}
+
+fn log_report(record: &CheckRecord) {
+    if let Some(logger) = REPORT_LOGGER.get() {
+        if let Ok(line) = serde_json::to_string(record) {
+            let mut report = logger.lock().unwrap();
+            writeln!(report, "{}", line).ok();
+        }
+    }
+}
 
 /// Graceful shutdown (Ctrl+C)
 async fn handle_signals() {
@@ -42,20 +270,27 @@ async fn handle_signals() {
     STOP_REQUESTED.store(true, Ordering::SeqCst);
 }
 
-/// Check if a URL is valid (status 200, no redirects)
-async fn check_url(client: &Client, base_template: &str, uid: u32, qnum: u32) -> Option<String> {
-    if STOP_REQUESTED.load(Ordering::SeqCst) {
-        return None;
-    }
-
+/// Check if a URL is valid (status 200; a redirect counts only under
+/// `--on-redirect follow`, when its chain ends in a 200), returning a typed
+/// `CheckResult` instead of discarding the status/error information.
+///
+/// Before every attempt, waits out the globally shared `MIN_INTERVAL_MS`
+/// interval and only then acquires a permit from `config.semaphore`, so a
+/// task that is still waiting does not hold a permit. The wait is per task:
+/// tasks wait side by side, so the interval is not a global gap between the
+/// requests of different tasks. The permit is held until the attempt's result
+/// is known, including a 429 back-off sleep, a body read and followed hops.
+async fn check_url(
+    client: &Client,
+    base_template: &str,
+    uid: u32,
+    qnum: u32,
+    config: &CheckConfig,
+) -> CheckResult {
     let url = base_template
         .replace("{uid}", &uid.to_string())
         .replace("{qnum}", &qnum.to_string());
 
-    // random delay between 100ms and 500ms
-    let delay = thread_rng().gen_range(100..500);
-    sleep(Duration::from_millis(delay)).await;
-
     // random user-agent
     let ua = USER_AGENTS[thread_rng().gen_range(0..USER_AGENTS.len())];
 
@@ -63,9 +298,24 @@ async fn check_url(client: &Client, base_template: &str, uid: u32, qnum: u32) ->
     let mut retries = 0;
     loop {
         if STOP_REQUESTED.load(Ordering::SeqCst) {
-            return None;
+            return CheckResult::Skipped;
+        }
+
+        // Respect the globally shared pacing interval before every attempt,
+        // and before acquiring a permit (see the doc comment above).
+        let interval = MIN_INTERVAL_MS.load(Ordering::SeqCst);
+        sleep(Duration::from_millis(interval)).await;
+
+        if STOP_REQUESTED.load(Ordering::SeqCst) {
+            return CheckResult::Skipped;
         }
 
+        let _permit = config
+            .semaphore
+            .acquire()
+            .await
+            .expect("concurrency semaphore closed");
+
         println!("Trying: {}", url);
 
         match client
@@ -79,6 +329,7 @@ async fn check_url(client: &Client, base_template: &str, uid: u32, qnum: u32) ->
                 let status = resp.status();
 
                 if status.as_u16() == 429 {
+                    record_rate_limited();
                     retries += 1;
                     if retries > 3 {
                         println!(
@@ -86,7 +337,7 @@ async fn check_url(client: &Client, base_template: &str, uid: u32, qnum: u32) ->
                             retries - 1,
                             url
                         );
-                        return None;
+                        return CheckResult::RateLimited { retries: retries - 1 };
                     }
                     let backoff = 15 * retries;
                     println!(
@@ -97,53 +348,326 @@ async fn check_url(client: &Client, base_template: &str, uid: u32, qnum: u32) ->
                     continue; // retry
                 }
 
+                if status == 200 || status.as_u16() == 404 {
+                    record_clean_response();
+                }
+
+                let location = resp
+                    .headers()
+                    .get(reqwest::header::LOCATION)
+                    .and_then(|v| v.to_str().ok())
+                    .map(|s| s.to_string());
+
                 if status == 200 && resp.url().as_str() == url {
+                    if let Some(soft_404) = check_soft_404(resp, config).await {
+                        return soft_404;
+                    }
                     println!("Found: {}", url);
                     let mut log = VALID_LOGGER.lock().unwrap();
                     writeln!(log, "{}", url).ok();
-                    return Some(url);
-                } else if status.as_u16() >= 400 {
+                    return CheckResult::Ok {
+                        url,
+                        status: status.as_u16(),
+                    };
+                }
+
+                if status.is_redirection() {
+                    let to = location.clone().unwrap_or_default();
+                    return match config.redirect_policy {
+                        RedirectPolicy::Follow => {
+                            follow_redirect(client, ua, url, to, status.as_u16(), 1, config).await
+                        }
+                        RedirectPolicy::Skip => {
+                            println!("[REDIRECT] {} -> {} ({})", url, to, status.as_u16());
+                            CheckResult::Redirect {
+                                from: url,
+                                to,
+                                status: status.as_u16(),
+                            }
+                        }
+                        RedirectPolicy::Fail => {
+                            println!("[REDIRECT] {} -> {} ({}) - failing, no retries", url, to, status.as_u16());
+                            CheckResult::RedirectFailed {
+                                from: url,
+                                to,
+                                status: status.as_u16(),
+                            }
+                        }
+                    };
+                }
+
+                if status.as_u16() >= 400 {
                     println!("[BAD] {} - {}", status, url);
                 }
 
-                return None;
+                return CheckResult::HttpError {
+                    status: status.as_u16(),
+                    location,
+                };
             }
             Err(err) => {
                 println!("[ERROR] {} - {}", url, err);
-                return None;
+                if err.is_timeout() {
+                    return CheckResult::Timeout;
+                }
+                return CheckResult::Transport {
+                    error: err.to_string(),
+                };
             }
         }
     }
 }
 
-/// Search for a valid URL for one question
-async fn find_valid_url_for_question(client: &Client, base_template: &str, qnum: u32) {
-    let mut futures: FuturesUnordered<_> = FuturesUnordered::new();
+/// Check a 200 response's body against `--match`/`--not-match`, returning
+/// `Some(SoftNotFound)` if the body disqualifies the candidate, or `None` if
+/// it's a genuine hit (or no patterns were configured).
+async fn check_soft_404(resp: reqwest::Response, config: &CheckConfig) -> Option<CheckResult> {
+    if config.match_regex.is_none() && config.not_match_regex.is_none() {
+        return None;
+    }
 
-    for uid in UID_START..UID_END {
-        if STOP_REQUESTED.load(Ordering::SeqCst) {
+    let status = resp.status().as_u16();
+    let body = match read_capped_body(resp, config.max_body_bytes).await {
+        Ok(body) => body,
+        Err(err) => {
+            return Some(CheckResult::Transport {
+                error: err.to_string(),
+            })
+        }
+    };
+    let text = String::from_utf8_lossy(&body);
+
+    if let Some(re) = &config.match_regex {
+        if !re.is_match(&text) {
+            return Some(CheckResult::SoftNotFound {
+                status,
+                reason: "body did not match --match pattern".to_string(),
+            });
+        }
+    }
+    if let Some(re) = &config.not_match_regex {
+        if re.is_match(&text) {
+            return Some(CheckResult::SoftNotFound {
+                status,
+                reason: "body matched --not-match pattern".to_string(),
+            });
+        }
+    }
+
+    None
+}
+
+/// Stream a response body, stopping once `max_bytes` have been read so a
+/// misconfigured target can't exhaust memory.
+async fn read_capped_body(mut resp: reqwest::Response, max_bytes: usize) -> reqwest::Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    while let Some(chunk) = resp.chunk().await? {
+        buf.extend_from_slice(&chunk);
+        if buf.len() >= max_bytes {
+            buf.truncate(max_bytes);
             break;
         }
+    }
+    Ok(buf)
+}
+
+/// Resolve a `Location` header value against the URL that produced it. Most
+/// servers send an absolute URL, but the HTTP spec permits a relative one
+/// (e.g. `/path`), which would otherwise fail to parse as a request target.
+fn resolve_redirect_target(base: &str, location: &str) -> String {
+    reqwest::Url::parse(base)
+        .and_then(|base_url| base_url.join(location))
+        .map(|resolved| resolved.to_string())
+        .unwrap_or_else(|_| location.to_string())
+}
+
+/// Re-issue the request against a redirect's `Location` target, chasing chained
+/// redirects up to `MAX_REDIRECT_HOPS` before giving up.
+///
+/// Mirrors `check_url`'s primary path for a 200 hit: the soft-404 body check,
+/// the `valid_urls.log` write, and the `resp.url() == target` equality check all
+/// apply here too, so a redirect-then-hit candidate is treated identically to
+/// a direct one. Each hop also respects the shared pacing floor and feeds
+/// clean (200/404) responses into `record_clean_response`, the same as the
+/// primary path, so redirect-chasing traffic isn't invisible to the adaptive
+/// pacer.
+async fn follow_redirect(
+    client: &Client,
+    ua: &str,
+    from: String,
+    mut to: String,
+    mut status: u16,
+    mut hop: u32,
+    config: &CheckConfig,
+) -> CheckResult {
+    let mut base = from.clone();
+    loop {
+        if to.is_empty() {
+            return CheckResult::Redirect { from, to, status };
+        }
+        let target = resolve_redirect_target(&base, &to);
+        if hop > MAX_REDIRECT_HOPS {
+            println!(
+                "[REDIRECT] giving up after {} hops, last hop {} ({})",
+                MAX_REDIRECT_HOPS, target, status
+            );
+            return CheckResult::Redirect { from, to: target, status };
+        }
+
+        // Same globally shared pacing floor the primary path respects: a
+        // followed redirect still puts a request on the wire.
+        let interval = MIN_INTERVAL_MS.load(Ordering::SeqCst);
+        sleep(Duration::from_millis(interval)).await;
+
+        println!("[REDIRECT] following hop {} -> {}", hop, target);
+
+        match client
+            .get(&target)
+            .header("User-Agent", ua)
+            .timeout(Duration::from_secs(5))
+            .send()
+            .await
+        {
+            Ok(resp) => {
+                let resp_status = resp.status();
+
+                if resp_status == 200 || resp_status.as_u16() == 404 {
+                    record_clean_response();
+                }
+
+                let location = resp
+                    .headers()
+                    .get(reqwest::header::LOCATION)
+                    .and_then(|v| v.to_str().ok())
+                    .map(|s| s.to_string());
+
+                if resp_status == 200 && resp.url().as_str() == target {
+                    if let Some(soft_404) = check_soft_404(resp, config).await {
+                        return soft_404;
+                    }
+                    println!("Found: {}", target);
+                    let mut log = VALID_LOGGER.lock().unwrap();
+                    writeln!(log, "{}", target).ok();
+                    return CheckResult::Ok {
+                        url: target,
+                        status: 200,
+                    };
+                }
 
-        while futures.len() >= MAX_CONCURRENCY {
-            if let Some(res) = futures.next().await {
-                if let Some(_url) = res {
-                    return; // Found a valid one
+                if resp_status.is_redirection() {
+                    let next = location.unwrap_or_default();
+                    hop += 1;
+                    status = resp_status.as_u16();
+                    base = target;
+                    to = next;
+                    continue;
                 }
+
+                return CheckResult::HttpError {
+                    status: resp_status.as_u16(),
+                    location,
+                };
+            }
+            Err(err) => {
+                if err.is_timeout() {
+                    return CheckResult::Timeout;
+                }
+                return CheckResult::Transport {
+                    error: err.to_string(),
+                };
             }
         }
+    }
+}
+
+/// Load a batch of URL templates from `--input <file>`.
+///
+/// A `.md` file is parsed as Markdown and every link target is harvested
+/// (the same technique the awesome-rust checker uses to pull URLs out of a
+/// README); anything else is treated as one template per line.
+fn load_templates(path: &str) -> Vec<String> {
+    let content = std::fs::read_to_string(path).expect("Unable to read input file");
+
+    if path.ends_with(".md") {
+        harvest_markdown_links(&content)
+    } else {
+        content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+/// Pull every link destination out of a Markdown document's `Event::Start(Tag::Link(..))` events.
+fn harvest_markdown_links(content: &str) -> Vec<String> {
+    use pulldown_cmark::{Event, Parser, Tag};
+
+    Parser::new(content)
+        .filter_map(|event| match event {
+            Event::Start(Tag::Link(_link_type, dest_url, _title)) => Some(dest_url.to_string()),
+            _ => None,
+        })
+        .collect()
+}
 
-        futures.push(check_url(client, base_template, uid, qnum));
+/// Search for a valid URL for one question.
+///
+/// `config.semaphore` is the *only* cap on in-flight requests: every uid in
+/// range is queued as a future up front, and the semaphore alone (shared with
+/// every other in-flight question) decides how many are actually on the wire
+/// at once, rather than layering a second per-question cap on top of it.
+/// Honors an on-disk checkpoint when `--resume` is set: a question already
+/// marked solved is skipped outright, and an in-progress one resumes from
+/// the lowest uid not yet confirmed checked instead of sweeping from the start.
+async fn find_valid_url_for_question(
+    client: &Client,
+    base_template: &str,
+    qnum: u32,
+    config: CheckConfig,
+) {
+    if is_question_solved(qnum) {
+        println!("Question {} already solved per checkpoint, skipping", qnum);
+        return;
     }
 
-    while let Some(res) = futures.next().await {
+    let mut futures: FuturesUnordered<_> = FuturesUnordered::new();
+    let start_uid = resume_uid(qnum);
+
+    for uid in start_uid..UID_END {
         if STOP_REQUESTED.load(Ordering::SeqCst) {
             break;
         }
-        if let Some(_url) = res {
+
+        let config = config.clone();
+        futures.push(async move {
+            let result = check_url(client, base_template, uid, qnum, &config).await;
+            (uid, result)
+        });
+    }
+
+    // Drain every future still in flight. Most of these were already sent
+    // over the wire, so their uid must be recorded or a later --resume would
+    // skip it for good — but any that hit the stop flag before ever sending
+    // a request come back as `Skipped` and must NOT be recorded: doing so
+    // would advance the resume floor past a uid that was never actually
+    // probed, which is exactly the failure `record_progress` exists to avoid.
+    while let Some((uid, result)) = futures.next().await {
+        if matches!(result, CheckResult::Skipped) {
+            continue;
+        }
+        record_progress(qnum, uid);
+        log_report(&CheckRecord { qnum, uid, result: result.clone() });
+        if let CheckResult::Ok { url, .. } = &result {
+            record_solved(qnum, url);
             return;
         }
     }
+
+    if STOP_REQUESTED.load(Ordering::SeqCst) {
+        save_checkpoint();
+    }
 }
 
 /// ## Usage
@@ -151,17 +675,36 @@ async fn find_valid_url_for_question(client: &Client, base_template: &str, qnum:
 /// ```bash
 /// cargo run --release \
 ///   "https://www.examtopics.com/discussions/splunk/view/{uid}-exam-splk-1003-topic-1-question-{qnum}-discussion/" \
-///   1
+///   1 --report results.jsonl
 /// ```
 ///
 /// - The first argument is the **base URL template** containing `{uid}` and `{qnum}` placeholders.
+///   Omit it when using `--input`.
 /// - The second argument (optional) is the **starting question number** (default: 1).
+/// - `--input <file>` batches a whole corpus of templates from a file instead of a single
+///   positional template: one per line, or every Markdown link target when the file ends in `.md`.
+/// - `--report <path>` writes every outcome (hits and misses alike) as JSON Lines to `<path>`,
+///   in addition to the existing `valid_urls.log`.
+/// - `--concurrency <n>` sets the global cap on in-flight requests, shared across every
+///   question being searched (default: 5).
+/// - `--on-redirect {skip|follow|fail}` controls how 3xx responses are treated: `skip` (default)
+///   records the redirect and moves on, `follow` re-issues the request against `Location` up to
+///   a bounded hop count, and `fail` marks the candidate dead immediately with no retries.
+/// - `--match <regex>` / `--not-match <regex>` inspect the first `--max-body-bytes` (default
+///   256 KiB) of a 200 response's body and demote the candidate to a soft-404 unless it matches
+///   (or fails to match) the given pattern.
+/// - `--resume <statefile>` checkpoints progress (per-question resume uid and solved URLs)
+///   to `<statefile>` as the scan runs, and picks up from it on the next run instead of
+///   starting over.
 #[tokio::main]
 async fn main() {
     let args: Vec<String> = std::env::args().collect();
 
     if args.len() < 2 {
-        eprintln!("Usage: {} <BASE_URL_TEMPLATE> [START_QNUM]", args[0]);
+        eprintln!(
+            "Usage: {} <BASE_URL_TEMPLATE|--input FILE> [START_QNUM] [--report <path>] [--concurrency <n>]",
+            args[0]
+        );
         eprintln!("Example:");
         eprintln!(
             "  {} \"https://www.examtopics.com/discussions/splunk/view/{{uid}}-exam-splk-1003-topic-1-question-{{qnum}}-discussion/\" 1",
@@ -170,28 +713,313 @@ async fn main() {
         std::process::exit(1);
     }
 
-    let base_template = &args[1];
-    let start_qnum = args
-        .get(2)
+    let mut positional = Vec::new();
+    let mut report_path: Option<String> = None;
+    let mut input_path: Option<String> = None;
+    let mut concurrency = DEFAULT_CONCURRENCY;
+    let mut redirect_policy = RedirectPolicy::Skip;
+    let mut match_regex: Option<Arc<Regex>> = None;
+    let mut not_match_regex: Option<Arc<Regex>> = None;
+    let mut max_body_bytes = DEFAULT_MAX_BODY_BYTES;
+    let mut resume_path: Option<String> = None;
+    let mut rest = args[1..].iter();
+    while let Some(arg) = rest.next() {
+        if arg == "--report" {
+            report_path = rest.next().cloned();
+        } else if arg == "--input" {
+            input_path = rest.next().cloned();
+        } else if arg == "--resume" {
+            resume_path = rest.next().cloned();
+        } else if arg == "--concurrency" {
+            concurrency = rest
+                .next()
+                .and_then(|s| s.parse::<usize>().ok())
+                .filter(|&v| v > 0)
+                .unwrap_or(DEFAULT_CONCURRENCY);
+        } else if arg == "--on-redirect" {
+            redirect_policy = rest
+                .next()
+                .and_then(|s| RedirectPolicy::parse(s))
+                .unwrap_or(RedirectPolicy::Skip);
+        } else if arg == "--match" {
+            match_regex = rest
+                .next()
+                .map(|s| Arc::new(Regex::new(s).expect("Invalid --match regex")));
+        } else if arg == "--not-match" {
+            not_match_regex = rest
+                .next()
+                .map(|s| Arc::new(Regex::new(s).expect("Invalid --not-match regex")));
+        } else if arg == "--max-body-bytes" {
+            max_body_bytes = rest
+                .next()
+                .and_then(|s| s.parse::<usize>().ok())
+                .filter(|&v| v > 0)
+                .unwrap_or(DEFAULT_MAX_BODY_BYTES);
+        } else {
+            positional.push(arg.clone());
+        }
+    }
+
+    let templates: Vec<String> = match &input_path {
+        Some(path) => load_templates(path),
+        None => match positional.first() {
+            Some(template) => vec![template.clone()],
+            None => {
+                eprintln!("Error: no BASE_URL_TEMPLATE given and no --input file provided.");
+                eprintln!(
+                    "Usage: {} <BASE_URL_TEMPLATE|--input FILE> [START_QNUM] [--report <path>] [--concurrency <n>]",
+                    args[0]
+                );
+                std::process::exit(1);
+            }
+        },
+    };
+    let start_qnum = positional
+        .get(if input_path.is_some() { 0 } else { 1 })
         .and_then(|s| s.parse::<u32>().ok())
         .filter(|&v| v > 0)
         .unwrap_or(1);
 
-    let client = Client::builder()
-        .redirect(reqwest::redirect::Policy::none())
-        .build()
-        .expect("Failed to create client");
+    if let Some(path) = report_path {
+        let file = File::create(&path).expect("Unable to create report file");
+        REPORT_LOGGER
+            .set(Mutex::new(file))
+            .expect("report logger already initialized");
+    }
+
+    if let Some(path) = resume_path {
+        let state = load_checkpoint(&path);
+        if CHECKPOINT_STORE
+            .set(CheckpointStore {
+                path,
+                state: Mutex::new(state),
+            })
+            .is_err()
+        {
+            panic!("checkpoint store already initialized");
+        }
+    }
+
+    let client = Arc::new(
+        Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .expect("Failed to create client"),
+    );
+    let config = CheckConfig {
+        semaphore: Arc::new(Semaphore::new(concurrency)),
+        redirect_policy,
+        match_regex,
+        not_match_regex,
+        max_body_bytes,
+    };
 
     // Spawn signal handler
     task::spawn(handle_signals());
 
-    for qnum in start_qnum..=QUESTION_COUNT {
+    // Each (template, question) pair gets its own task so requests across the
+    // whole batch can be in flight at once; the shared semaphore in `config`
+    // is what keeps the combined rate of all of them bounded.
+    let mut handles = Vec::new();
+    for template in templates {
         if STOP_REQUESTED.load(Ordering::SeqCst) {
             break;
         }
-        println!("Searching for Question {}...", qnum);
-        find_valid_url_for_question(&client, base_template, qnum).await;
+
+        if !template.contains("{uid}") {
+            // No {uid} placeholder means there's no uid range to sweep: check
+            // the URL directly (once per qnum if {qnum} is present, otherwise
+            // once) instead of falling into find_valid_url_for_question and
+            // building the same fixed URL ~70,000 times over.
+            let template = Arc::new(template);
+            let qnums: Vec<u32> = if template.contains("{qnum}") {
+                (start_qnum..=QUESTION_COUNT).collect()
+            } else {
+                vec![0]
+            };
+            for qnum in qnums {
+                if STOP_REQUESTED.load(Ordering::SeqCst) {
+                    break;
+                }
+                let client = client.clone();
+                let template = template.clone();
+                let config = config.clone();
+                handles.push(task::spawn(async move {
+                    println!("Checking question {}: {}", qnum, template);
+                    let result = check_url(&client, &template, 0, qnum, &config).await;
+                    if !matches!(result, CheckResult::Skipped) {
+                        log_report(&CheckRecord { qnum, uid: 0, result });
+                    }
+                }));
+            }
+            continue;
+        }
+
+        let template = Arc::new(template);
+        let qnums: Vec<u32> = if template.contains("{qnum}") {
+            (start_qnum..=QUESTION_COUNT).collect()
+        } else {
+            vec![start_qnum]
+        };
+
+        for qnum in qnums {
+            if STOP_REQUESTED.load(Ordering::SeqCst) {
+                break;
+            }
+            let client = client.clone();
+            let template = template.clone();
+            let config = config.clone();
+            handles.push(task::spawn(async move {
+                println!("Searching for Question {}...", qnum);
+                find_valid_url_for_question(&client, &template, qnum, config).await;
+            }));
+        }
+    }
+
+    for handle in handles {
+        handle.await.ok();
     }
 
     println!("Exiting.");
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Bind a one-shot local server that replies with `body` to the first
+    /// connection it accepts, so `read_capped_body` can be exercised against
+    /// a real `reqwest::Response` without a mocking dependency.
+    fn spawn_test_server(body: String) -> std::net::SocketAddr {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            use std::io::Read;
+            if let Ok((mut stream, _)) = listener.accept() {
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf);
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn read_capped_body_truncates_at_max_bytes() {
+        let body = "x".repeat(1000);
+        let addr = spawn_test_server(body);
+        let resp = Client::new()
+            .get(format!("http://{}/", addr))
+            .send()
+            .await
+            .unwrap();
+
+        let capped = read_capped_body(resp, 100).await.unwrap();
+
+        assert_eq!(capped.len(), 100);
+    }
+
+    #[tokio::test]
+    async fn read_capped_body_returns_full_body_under_the_cap() {
+        let body = "y".repeat(50);
+        let addr = spawn_test_server(body.clone());
+        let resp = Client::new()
+            .get(format!("http://{}/", addr))
+            .send()
+            .await
+            .unwrap();
+
+        let capped = read_capped_body(resp, 100).await.unwrap();
+
+        assert_eq!(capped, body.into_bytes());
+    }
+
+    #[test]
+    fn checkpoint_round_trips_through_json() {
+        let mut checkpoint = Checkpoint::default();
+        checkpoint.progress.insert(1, 10_042);
+        checkpoint.pending.insert(1, [10_044, 10_045].into_iter().collect());
+        checkpoint.solved.insert(2);
+        checkpoint.found_urls.insert(2, "https://example.com/found".to_string());
+
+        let json = serde_json::to_string(&checkpoint).unwrap();
+        let restored: Checkpoint = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.progress.get(&1), Some(&10_042));
+        assert_eq!(
+            restored.pending.get(&1),
+            Some(&[10_044, 10_045].into_iter().collect())
+        );
+        assert!(restored.solved.contains(&2));
+        assert_eq!(
+            restored.found_urls.get(&2),
+            Some(&"https://example.com/found".to_string())
+        );
+    }
+
+    #[test]
+    fn redirect_policy_parses_known_values_and_rejects_unknown() {
+        assert!(matches!(RedirectPolicy::parse("skip"), Some(RedirectPolicy::Skip)));
+        assert!(matches!(RedirectPolicy::parse("follow"), Some(RedirectPolicy::Follow)));
+        assert!(matches!(RedirectPolicy::parse("fail"), Some(RedirectPolicy::Fail)));
+        assert!(RedirectPolicy::parse("bogus").is_none());
+    }
+
+    #[test]
+    fn resolve_redirect_target_joins_relative_location_against_base() {
+        assert_eq!(
+            resolve_redirect_target("https://example.com/a/b", "/c"),
+            "https://example.com/c"
+        );
+        assert_eq!(
+            resolve_redirect_target("https://example.com/a/b", "https://other.example/c"),
+            "https://other.example/c"
+        );
+    }
+
+    #[test]
+    fn harvest_markdown_links_pulls_every_link_target() {
+        let content = "# Links\n\n- [one](https://example.com/one)\n- [two](https://example.com/two)\n\nplain text, no link here.\n";
+        let links = harvest_markdown_links(content);
+        assert_eq!(
+            links,
+            vec![
+                "https://example.com/one".to_string(),
+                "https://example.com/two".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn load_templates_reads_one_template_per_line_for_non_markdown() {
+        let path = std::env::temp_dir().join(format!("rust_url_getter_test_{}.txt", std::process::id()));
+        std::fs::write(&path, "https://a.example/{uid}\n\n  https://b.example/{uid}  \n").unwrap();
+
+        let templates = load_templates(path.to_str().unwrap());
+
+        std::fs::remove_file(&path).ok();
+        assert_eq!(
+            templates,
+            vec![
+                "https://a.example/{uid}".to_string(),
+                "https://b.example/{uid}".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn load_templates_harvests_markdown_links_for_md_files() {
+        let path = std::env::temp_dir().join(format!("rust_url_getter_test_{}.md", std::process::id()));
+        std::fs::write(&path, "[example](https://example.com/{uid})\n").unwrap();
+
+        let templates = load_templates(path.to_str().unwrap());
+
+        std::fs::remove_file(&path).ok();
+        assert_eq!(templates, vec!["https://example.com/{uid}".to_string()]);
+    }
+}